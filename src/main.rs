@@ -1,8 +1,11 @@
 use core::fmt;
 use std::{
+    cell::RefCell,
     collections::HashMap,
+    fs,
     io::{self, Write},
     num::ParseFloatError,
+    rc::Rc,
 };
 
 //Type Definitions
@@ -11,26 +14,177 @@ enum RispExp {
     Bool(bool),
     Symbol(String),
     Number(f64),
+    Str(String),
     List(Vec<RispExp>),
     Func(fn(&[RispExp]) -> Result<RispExp, RispErr>),
+    Lambda(RispLambda),
 }
+
+// hand-written rather than derived: a derived impl would compare `Func`
+// variants by function pointer, but pointer addresses for the same fn item
+// aren't guaranteed unique across codegen units, so that equality would be
+// meaningless (and trips clippy's `unpredictable_function_pointer_comparisons`
+// lint). Two functions are just never equal here.
+impl PartialEq for RispExp {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (RispExp::Bool(a), RispExp::Bool(b)) => a == b,
+            (RispExp::Symbol(a), RispExp::Symbol(b)) => a == b,
+            (RispExp::Number(a), RispExp::Number(b)) => a == b,
+            (RispExp::Str(a), RispExp::Str(b)) => a == b,
+            (RispExp::List(a), RispExp::List(b)) => a == b,
+            (RispExp::Lambda(a), RispExp::Lambda(b)) => a == b,
+            (RispExp::Func(_), RispExp::Func(_)) => false,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct RispLambda {
+    params_exp: Rc<RispExp>,
+    body_exp: Rc<RispExp>,
+    // the environment the lambda was defined in, captured once so that every
+    // call re-enters the same scope rather than chaining onto whichever
+    // dynamic call site happens to invoke it; without this, a self-recursive
+    // tail call would grow the env chain by one scope per call and defeat
+    // the point of the `eval` trampoline below. Note: a lambda that closes
+    // over the very scope it gets `def`-ed into forms an `Rc` cycle, which
+    // this interpreter has no collector for; an acceptable tradeoff for a
+    // tree-walking interpreter with no GC story in the first place.
+    env: EnvRef,
+}
+
+// equality ignores the captured environment (two lambdas built from the
+// same source forms are the same lambda for `=` purposes, regardless of
+// which scope closed over them)
+impl PartialEq for RispLambda {
+    fn eq(&self, other: &Self) -> bool {
+        self.params_exp == other.params_exp && self.body_exp == other.body_exp
+    }
+}
+
 #[derive(Debug)]
 enum RispErr {
     Reason(String),
 }
+
 #[derive(Clone)]
 struct RispEnv {
     data: HashMap<String, RispExp>,
+    outer: Option<EnvRef>,
 }
 
+// a scope is shared (an env can be captured by multiple lambdas) and needs
+// to be mutated in place by `def`, so it's kept behind `Rc<RefCell<_>>`
+// rather than the borrowed-lifetime chain this used to be; that, in turn, is
+// what lets `eval` rebind its working env across loop iterations instead of
+// recursing into a new Rust stack frame for every tail call
+type EnvRef = Rc<RefCell<RispEnv>>;
+
 //Parsing
 //tokenize("(+ 10 5)") //=> ["(", "+", "10", "5", ")"]
-fn tokenize(expr: String) -> Vec<String> {
-    expr.replace("(", " ( ")
-        .replace(")", " ) ")
-        .split_whitespace()
-        .map(|x| x.to_string())
-        .collect()
+//
+// scans character by character (rather than just padding parens and
+// splitting on whitespace) so that string literals and comments can span
+// whitespace and parens without being torn apart.
+fn tokenize(expr: String) -> Result<Vec<String>, RispErr> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut tokens: Vec<String> = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            ';' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '(' | ')' | '\'' => {
+                tokens.push(chars[i].to_string());
+                i += 1;
+            }
+            c if c.is_whitespace() => {
+                i += 1;
+            }
+            '"' => {
+                let mut s = String::from("\"");
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' && i + 1 < chars.len() {
+                        s.push(match chars[i + 1] {
+                            'n' => '\n',
+                            '"' => '"',
+                            '\\' => '\\',
+                            other => other,
+                        });
+                        i += 2;
+                    } else {
+                        s.push(chars[i]);
+                        i += 1;
+                    }
+                }
+                i += 1; // consume the closing quote
+                s.push('"');
+                tokens.push(s);
+            }
+            _ => {
+                let start = i;
+                while i < chars.len()
+                    && !chars[i].is_whitespace()
+                    && !matches!(chars[i], '(' | ')' | ';' | '"' | '\'')
+                {
+                    i += 1;
+                }
+                tokens.push(chars[start..i].iter().collect());
+            }
+        }
+    }
+    expand_quote_sugar(tokens)
+}
+
+// rewrites a standalone `'` token into `( quote ... )` wrapped around the
+// single form that follows it, so `'(1 2 3)` reads the same as
+// `(quote (1 2 3))`
+fn expand_quote_sugar(tokens: Vec<String>) -> Result<Vec<String>, RispErr> {
+    let mut out: Vec<String> = vec![];
+    let mut i = 0;
+    while i < tokens.len() {
+        if tokens[i] == "'" {
+            i += 1;
+            let start = i;
+            while tokens.get(i).map(|t| t.as_str()) == Some("'") {
+                i += 1;
+            }
+            if tokens.get(i).map(|t| t.as_str()) == Some("(") {
+                let mut depth = 0;
+                loop {
+                    let token = tokens
+                        .get(i)
+                        .ok_or(RispErr::Reason("unbalanced parens after quote".to_string()))?;
+                    if token == "(" {
+                        depth += 1;
+                    } else if token == ")" {
+                        depth -= 1;
+                    }
+                    i += 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            } else if i < tokens.len() {
+                i += 1;
+            }
+            // the quoted form may itself contain `'` sugar, so expand it too
+            out.push("(".to_string());
+            out.push("quote".to_string());
+            out.extend(expand_quote_sugar(tokens[start..i].to_vec())?);
+            out.push(")".to_string());
+        } else {
+            out.push(tokens[i].clone());
+            i += 1;
+        }
+    }
+    Ok(out)
 }
 
 fn parse(tokens: &[String]) -> Result<(RispExp, &[String]), RispErr> {
@@ -44,6 +198,19 @@ fn parse(tokens: &[String]) -> Result<(RispExp, &[String]), RispErr> {
     }
 }
 
+// parses every top-level form in `tokens` in sequence, rather than just the
+// first one, so a whole file of `.risp` source can be read at once
+fn parse_all(tokens: &[String]) -> Result<Vec<RispExp>, RispErr> {
+    let mut exps: Vec<RispExp> = vec![];
+    let mut rest = tokens;
+    while !rest.is_empty() {
+        let (exp, new_rest) = parse(rest)?;
+        exps.push(exp);
+        rest = new_rest;
+    }
+    Ok(exps)
+}
+
 // reading and parsing the tokens which follows current opening parenthesis,
 // until we hit a closing parenthesis:
 fn read_seq(tokens: &[String]) -> Result<(RispExp, &[String]), RispErr> {
@@ -67,6 +234,9 @@ fn parse_atom(token: &str) -> RispExp {
         "true" => RispExp::Bool(true),
         "false" => RispExp::Bool(false),
         _ => {
+            if token.len() >= 2 && token.starts_with('"') && token.ends_with('"') {
+                return RispExp::Str(token[1..token.len() - 1].to_string());
+            }
             let potential_float: Result<f64, ParseFloatError> = token.parse();
             match potential_float {
                 Ok(v) => RispExp::Number(v),
@@ -87,6 +257,50 @@ fn parse_single_float(exp: &RispExp) -> Result<f64, RispErr> {
     }
 }
 
+fn parse_two_floats(args: &[RispExp]) -> Result<(f64, f64), RispErr> {
+    match parse_list_of_floats(args)?.as_slice() {
+        [a, b] => Ok((*a, *b)),
+        _ => Err(RispErr::Reason("expected exactly two numbers".to_string())),
+    }
+}
+
+fn risp_car(args: &[RispExp]) -> Result<RispExp, RispErr> {
+    match args {
+        [RispExp::List(l)] => l
+            .first()
+            .cloned()
+            .ok_or(RispErr::Reason("called car on an empty list".to_string())),
+        _ => Err(RispErr::Reason("car expects a single list argument".to_string())),
+    }
+}
+
+fn risp_cdr(args: &[RispExp]) -> Result<RispExp, RispErr> {
+    match args {
+        [RispExp::List(l)] => Ok(l
+            .split_first()
+            .map(|(_, tail)| RispExp::List(tail.to_vec()))
+            .unwrap_or(RispExp::List(vec![]))),
+        _ => Err(RispErr::Reason("cdr expects a single list argument".to_string())),
+    }
+}
+
+fn parse_list_of_symbol_strings(form: Rc<RispExp>) -> Result<Vec<String>, RispErr> {
+    let list = match form.as_ref() {
+        RispExp::List(s) => Ok(s.clone()),
+        _ => Err(RispErr::Reason(
+            "expected args form to be a list".to_string(),
+        )),
+    }?;
+    list.iter()
+        .map(|x| match x {
+            RispExp::Symbol(s) => Ok(s.clone()),
+            _ => Err(RispErr::Reason(
+                "expected symbols in the argument list".to_string(),
+            )),
+        })
+        .collect()
+}
+
 // Environment
 
 macro_rules! ensure_tonicity {
@@ -108,7 +322,7 @@ macro_rules! ensure_tonicity {
     }};
 }
 
-fn default_env() -> RispEnv {
+fn default_env() -> EnvRef {
     let mut data: HashMap<String, RispExp> = HashMap::new();
     // add "+" func
     data.insert(
@@ -133,10 +347,64 @@ fn default_env() -> RispEnv {
         }),
     );
 
+    // add "*" func
+    data.insert(
+        "*".to_string(),
+        RispExp::Func(|args: &[RispExp]| -> Result<RispExp, RispErr> {
+            let product = parse_list_of_floats(args)?
+                .iter()
+                .fold(1.0, |product, a| product * a);
+            Ok(RispExp::Number(product))
+        }),
+    );
+    // add "/" func
+    data.insert(
+        "/".to_string(),
+        RispExp::Func(|args: &[RispExp]| -> Result<RispExp, RispErr> {
+            let floats = parse_list_of_floats(args)?;
+            let first = *floats
+                .first()
+                .ok_or(RispErr::Reason("expexted at least one number".to_string()))?;
+            floats[1..]
+                .iter()
+                .try_fold(first, |quotient, divisor| {
+                    if *divisor == 0.0 {
+                        Err(RispErr::Reason("division by zero".to_string()))
+                    } else {
+                        Ok(quotient / divisor)
+                    }
+                })
+                .map(RispExp::Number)
+        }),
+    );
+    // add "mod" func
+    data.insert(
+        "mod".to_string(),
+        RispExp::Func(|args: &[RispExp]| -> Result<RispExp, RispErr> {
+            let (a, b) = parse_two_floats(args)?;
+            Ok(RispExp::Number(a.rem_euclid(b)))
+        }),
+    );
+    // add "pow" func
+    data.insert(
+        "pow".to_string(),
+        RispExp::Func(|args: &[RispExp]| -> Result<RispExp, RispErr> {
+            let (a, b) = parse_two_floats(args)?;
+            Ok(RispExp::Number(a.powf(b)))
+        }),
+    );
+
     //Bool functions
+    // "=" compares via `RispExp`'s `PartialEq` rather than `ensure_tonicity!` so
+    // that strings and lists, not just numbers, can be compared for equality
     data.insert(
         "=".to_string(),
-        RispExp::Func(ensure_tonicity!(|a, b| a == b)),
+        RispExp::Func(|args: &[RispExp]| -> Result<RispExp, RispErr> {
+            let first = args
+                .first()
+                .ok_or(RispErr::Reason("expected at least one argument".to_string()))?;
+            Ok(RispExp::Bool(args.iter().all(|x| x == first)))
+        }),
     );
     data.insert(
         ">".to_string(),
@@ -155,37 +423,333 @@ fn default_env() -> RispEnv {
         RispExp::Func(ensure_tonicity!(|a, b| a <= b)),
     );
 
-    RispEnv { data }
+    // "and"/"or" are special forms (see `eval_built_in_form`) rather than
+    // `Func`s, so that unneeded operands are never evaluated
+
+    // list primitives
+    data.insert(
+        "list".to_string(),
+        RispExp::Func(|args: &[RispExp]| -> Result<RispExp, RispErr> { Ok(RispExp::List(args.to_vec())) }),
+    );
+    data.insert("car".to_string(), RispExp::Func(risp_car));
+    data.insert("first".to_string(), RispExp::Func(risp_car));
+    data.insert("cdr".to_string(), RispExp::Func(risp_cdr));
+    data.insert("rest".to_string(), RispExp::Func(risp_cdr));
+    data.insert(
+        "cons".to_string(),
+        RispExp::Func(|args: &[RispExp]| -> Result<RispExp, RispErr> {
+            match args {
+                [head, RispExp::List(tail)] => {
+                    let mut new_list = Vec::with_capacity(tail.len() + 1);
+                    new_list.push(head.clone());
+                    new_list.extend(tail.iter().cloned());
+                    Ok(RispExp::List(new_list))
+                }
+                _ => Err(RispErr::Reason("cons expects an element and a list".to_string())),
+            }
+        }),
+    );
+    data.insert(
+        "atom".to_string(),
+        RispExp::Func(|args: &[RispExp]| -> Result<RispExp, RispErr> {
+            match args {
+                [RispExp::List(l)] => Ok(RispExp::Bool(l.is_empty())),
+                [_] => Ok(RispExp::Bool(true)),
+                _ => Err(RispErr::Reason("atom expects exactly one argument".to_string())),
+            }
+        }),
+    );
+
+    // add "print" func
+    data.insert(
+        "print".to_string(),
+        RispExp::Func(|args: &[RispExp]| -> Result<RispExp, RispErr> {
+            let strs: Vec<String> = args
+                .iter()
+                .map(|x| match x {
+                    RispExp::Str(s) => s.clone(),
+                    _ => x.to_string(),
+                })
+                .collect();
+            println!("{}", strs.join(" "));
+            Ok(RispExp::Bool(true))
+        }),
+    );
+
+    let env = Rc::new(RefCell::new(RispEnv { data, outer: None }));
+    load_stdlib(&env);
+    env
 }
 
-//Evaluation
-fn eval(exp: &RispExp, env: &mut RispEnv) -> Result<RispExp, RispErr> {
+// the parts of the standard library that are easier to express in Risp
+// itself than to hand-code as Rust `Func`s, bootstrapped via the same
+// lambda/def machinery a user program would use
+const STDLIB: &str = "
+    (def not (fn (a) (if a false true)))
+    (def inc (fn (a) (+ a 1)))
+    (def reduce (fn (f acc xs)
+        (if (atom xs)
+            acc
+            (reduce f (f acc (car xs)) (cdr xs)))))
+    (def map (fn (f xs)
+        (if (atom xs)
+            (quote ())
+            (cons (f (car xs)) (map f (cdr xs))))))
+    (def filter (fn (pred xs)
+        (if (atom xs)
+            (quote ())
+            (if (pred (car xs))
+                (cons (car xs) (filter pred (cdr xs)))
+                (filter pred (cdr xs))))))
+";
+
+fn load_stdlib(env: &EnvRef) {
+    let tokens = tokenize(STDLIB.to_string()).expect("stdlib failed to tokenize");
+    let exps = parse_all(&tokens).expect("stdlib failed to parse");
+    for exp in &exps {
+        eval(exp, env).expect("stdlib failed to evaluate");
+    }
+}
+
+// walks outward through enclosing scopes until it finds `k`, or runs out of
+// envs; an explicit loop rather than recursion. The chain is only as deep as
+// the lexical nesting of the lambdas involved, since each call scope's outer
+// env is the lambda's definition env (see `env_for_lambda`), not its caller
+fn env_get(k: &str, env: &EnvRef) -> Option<RispExp> {
+    let mut env = Rc::clone(env);
+    loop {
+        if let Some(exp) = env.borrow().data.get(k) {
+            return Some(exp.clone());
+        }
+        let outer = env.borrow().outer.clone();
+        match outer {
+            Some(outer_env) => env = outer_env,
+            None => return None,
+        }
+    }
+}
+
+fn eval_forms(arg_forms: &[RispExp], env: &EnvRef) -> Result<Vec<RispExp>, RispErr> {
+    arg_forms.iter().map(|x| eval(x, env)).collect()
+}
+
+// binds the lambda's params to the args (evaluated in the calling scope) in
+// a fresh scope whose outer env is the lambda's own captured definition env
+// -- *not* the calling env -- so that a chain of self-recursive calls stays
+// one scope deep instead of growing with call depth
+fn env_for_lambda(
+    lambda: &RispLambda,
+    arg_forms: &[RispExp],
+    call_env: &EnvRef,
+) -> Result<EnvRef, RispErr> {
+    let ks = parse_list_of_symbol_strings(Rc::clone(&lambda.params_exp))?;
+    if ks.len() != arg_forms.len() {
+        return Err(RispErr::Reason(format!(
+            "expected {} arguments, got {}",
+            ks.len(),
+            arg_forms.len()
+        )));
+    }
+    let vs = eval_forms(arg_forms, call_env)?;
+    let mut data: HashMap<String, RispExp> = HashMap::new();
+    for (k, v) in ks.iter().zip(vs.iter()) {
+        data.insert(k.clone(), v.clone());
+    }
+    Ok(Rc::new(RefCell::new(RispEnv {
+        data,
+        outer: Some(Rc::clone(&lambda.env)),
+    })))
+}
+
+fn eval_fn_args(arg_forms: &[RispExp], env: &EnvRef) -> Result<RispLambda, RispErr> {
+    let params_exp = arg_forms
+        .first()
+        .ok_or(RispErr::Reason("expected args form".to_string()))?;
+    let body_exp = arg_forms
+        .get(1)
+        .ok_or(RispErr::Reason("expected second form".to_string()))?;
+    if arg_forms.len() > 2 {
+        return Err(RispErr::Reason(
+            "fn definition can only have two forms".to_string(),
+        ));
+    }
+
+    Ok(RispLambda {
+        params_exp: Rc::new(params_exp.clone()),
+        body_exp: Rc::new(body_exp.clone()),
+        env: Rc::clone(env),
+    })
+}
+
+// evaluates only the test (a non-tail position) and returns the *unevaluated*
+// chosen branch so the `eval` trampoline can continue on it in tail position,
+// rather than recursing into it here
+fn eval_if_branch(arg_forms: &[RispExp], env: &EnvRef) -> Result<RispExp, RispErr> {
+    let test_form = arg_forms
+        .first()
+        .ok_or(RispErr::Reason("expected test form".to_string()))?;
+    let test_eval = eval(test_form, env)?;
+    match test_eval {
+        RispExp::Bool(b) => {
+            let form_idx = if b { 1 } else { 2 };
+            let res_form = arg_forms
+                .get(form_idx)
+                .ok_or(RispErr::Reason(format!("expected form idx={}", form_idx)))?;
+            Ok(res_form.clone())
+        }
+        _ => Err(RispErr::Reason(format!(
+            "unexpected test form='{}'",
+            test_form
+        ))),
+    }
+}
+
+// evaluates each operand in order, stopping as soon as one is `false` --
+// unlike a plain `Func`, whose arguments are all evaluated up front by
+// `eval_forms` before the builtin ever runs, this lets `(and false (bomb))`
+// skip `(bomb)` entirely
+fn eval_and_args(arg_forms: &[RispExp], env: &EnvRef) -> Result<RispExp, RispErr> {
+    for form in arg_forms {
+        match eval(form, env)? {
+            RispExp::Bool(false) => return Ok(RispExp::Bool(false)),
+            RispExp::Bool(true) => continue,
+            other => return Err(RispErr::Reason(format!("unexpected form='{}'", other))),
+        }
+    }
+    Ok(RispExp::Bool(true))
+}
+
+// mirrors `eval_and_args`, short-circuiting on the first `true` operand
+fn eval_or_args(arg_forms: &[RispExp], env: &EnvRef) -> Result<RispExp, RispErr> {
+    for form in arg_forms {
+        match eval(form, env)? {
+            RispExp::Bool(true) => return Ok(RispExp::Bool(true)),
+            RispExp::Bool(false) => continue,
+            other => return Err(RispErr::Reason(format!("unexpected form='{}'", other))),
+        }
+    }
+    Ok(RispExp::Bool(false))
+}
+
+fn eval_quote_args(arg_forms: &[RispExp]) -> Result<RispExp, RispErr> {
+    match arg_forms {
+        [form] => Ok(form.clone()),
+        _ => Err(RispErr::Reason("quote expects exactly one form".to_string())),
+    }
+}
+
+fn eval_def_args(arg_forms: &[RispExp], env: &EnvRef) -> Result<RispExp, RispErr> {
+    let first_form = arg_forms
+        .first()
+        .ok_or(RispErr::Reason("expected first form".to_string()))?;
+    let first_str = match first_form {
+        RispExp::Symbol(s) => Ok(s.clone()),
+        _ => Err(RispErr::Reason(
+            "expected first form to be a symbol".to_string(),
+        )),
+    }?;
+    let second_form = arg_forms
+        .get(1)
+        .ok_or(RispErr::Reason("expected second form".to_string()))?;
+    if arg_forms.len() > 2 {
+        return Err(RispErr::Reason(
+            "def can only have two forms".to_string(),
+        ));
+    }
+    let second_eval = eval(second_form, env)?;
+    env.borrow_mut().data.insert(first_str, second_eval.clone());
+
+    Ok(second_eval)
+}
+
+// what a special form hands back to the `eval` trampoline: either a final
+// `Value` (the special form is done), or a `Tail` expression+env pair to
+// keep looping on instead of recursing into (this is what lets `if`'s taken
+// branch stay in tail position)
+enum EvalOutcome {
+    Value(RispExp),
+    Tail(RispExp, EnvRef),
+}
+
+// dispatches on the head symbol of a list before falling through to function
+// application, so special forms can decide which of their arguments get
+// evaluated (or whether `env` itself should be mutated)
+fn eval_built_in_form(
+    exp: &RispExp,
+    arg_forms: &[RispExp],
+    env: &EnvRef,
+) -> Option<Result<EvalOutcome, RispErr>> {
     match exp {
-        RispExp::Bool(_a) => Ok(exp.clone()),
-        RispExp::Symbol(k) => env
-            .data
-            .get(k)
-            .ok_or(RispErr::Reason(format!("unexpected symbol k='{}'", k)))
-            .map(|x| x.clone()),
-        RispExp::Number(_) => Ok(exp.clone()),
-        RispExp::List(list) => {
-            let first_form = list
-                .first()
-                .ok_or(RispErr::Reason("expeceted a non-empty list".to_string()))?;
-            let arg_forms = &list[1..];
-            let first_eval = eval(first_form, env)?;
-            match first_eval {
-                RispExp::Func(f) => {
-                    let args_eval = arg_forms
-                        .iter()
-                        .map(|x| eval(x, env))
-                        .collect::<Result<Vec<RispExp>, RispErr>>();
-                    f(&args_eval?)
+        RispExp::Symbol(s) => match s.as_ref() {
+            "def" => Some(eval_def_args(arg_forms, env).map(EvalOutcome::Value)),
+            "if" => Some(
+                eval_if_branch(arg_forms, env).map(|branch| EvalOutcome::Tail(branch, Rc::clone(env))),
+            ),
+            // "lambda" is an alias for "fn" (the original chunk0-1 request's
+            // syntax), kept around since both spellings are idiomatic Lisp
+            "fn" | "lambda" => {
+                Some(eval_fn_args(arg_forms, env).map(RispExp::Lambda).map(EvalOutcome::Value))
+            }
+            "quote" => Some(eval_quote_args(arg_forms).map(EvalOutcome::Value)),
+            "and" => Some(eval_and_args(arg_forms, env).map(EvalOutcome::Value)),
+            "or" => Some(eval_or_args(arg_forms, env).map(EvalOutcome::Value)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+//Evaluation
+//
+// a `loop` rather than a recursive function: special forms whose result is a
+// tail-position expression (`if`'s taken branch) and lambda applications
+// (the body of the called function) rebind `exp`/`env` and `continue`
+// instead of calling `eval` again, so a self-recursive Risp function whose
+// body is a single tail call runs in constant native stack space
+fn eval(exp: &RispExp, env: &EnvRef) -> Result<RispExp, RispErr> {
+    let mut exp = exp.clone();
+    let mut env = Rc::clone(env);
+    loop {
+        match &exp {
+            RispExp::Bool(_a) => return Ok(exp.clone()),
+            RispExp::Symbol(k) => {
+                return env_get(k, &env).ok_or(RispErr::Reason(format!("unexpected symbol k='{}'", k)))
+            }
+            RispExp::Number(_) => return Ok(exp.clone()),
+            RispExp::Str(_) => return Ok(exp.clone()),
+            RispExp::List(list) => {
+                let first_form = list
+                    .first()
+                    .ok_or(RispErr::Reason("expeceted a non-empty list".to_string()))?
+                    .clone();
+                let arg_forms = list[1..].to_vec();
+
+                match eval_built_in_form(&first_form, &arg_forms, &env) {
+                    Some(Ok(EvalOutcome::Value(v))) => return Ok(v),
+                    Some(Ok(EvalOutcome::Tail(tail_exp, tail_env))) => {
+                        exp = tail_exp;
+                        env = tail_env;
+                        continue;
+                    }
+                    Some(Err(e)) => return Err(e),
+                    None => {
+                        let first_eval = eval(&first_form, &env)?;
+                        match first_eval {
+                            RispExp::Func(f) => return f(&eval_forms(&arg_forms, &env)?),
+                            RispExp::Lambda(lambda) => {
+                                let new_env = env_for_lambda(&lambda, &arg_forms, &env)?;
+                                exp = (*lambda.body_exp).clone();
+                                env = new_env;
+                                continue;
+                            }
+                            _ => return Err(RispErr::Reason("first form must be a function".to_string())),
+                        }
+                    }
                 }
-                _ => Err(RispErr::Reason("first form must be a function".to_string())),
             }
+            RispExp::Func(_) => return Err(RispErr::Reason("unexpected form".to_string())),
+            RispExp::Lambda(_) => return Err(RispErr::Reason("unexpected form".to_string())),
         }
-        RispExp::Func(_) => Err(RispErr::Reason("unexpected form".to_string())),
     }
 }
 
@@ -196,19 +760,21 @@ impl fmt::Display for RispExp {
             RispExp::Bool(a) => a.to_string(),
             RispExp::Symbol(s) => s.clone(),
             RispExp::Number(n) => n.to_string(),
+            RispExp::Str(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
             RispExp::List(list) => {
                 let xs = list.iter().map(|x| x.to_string()).collect::<Vec<String>>();
                 format!("({})", xs.join(","))
             }
-            RispExp::Func(_) => "Function {}".to_string(), //ã“ã“å‘¼ã°ã‚Œã‚‹ã“ã¨ã‚ã‚‹ã‚“ã‹ï¼Ÿ
+            RispExp::Func(_) => "Function {}".to_string(), //ここ呼ばれることあるんか？
+            RispExp::Lambda(_) => "Lambda {}".to_string(),
         };
 
         write!(f, "{}", str)
     }
 }
 
-fn parse_and_eval(expr: String, env: &mut RispEnv) -> Result<RispExp, RispErr> {
-    let (parsed_exp, _) = parse(&tokenize(expr))?;
+fn parse_and_eval(expr: String, env: &EnvRef) -> Result<RispExp, RispErr> {
+    let (parsed_exp, _) = parse(&tokenize(expr)?)?;
     let evaled_exp = eval(&parsed_exp, env)?;
 
     Ok(evaled_exp)
@@ -222,8 +788,20 @@ fn read_input_expr() -> String {
     expr
 }
 
-fn main() {
-    let env = &mut default_env();
+// reads a whole `.risp` file and evaluates every top-level form in it in
+// order against `env`, returning the value of the last one
+fn run_file(path: &str, env: &EnvRef) -> Result<RispExp, RispErr> {
+    let src = fs::read_to_string(path)
+        .map_err(|e| RispErr::Reason(format!("could not read '{}': {}", path, e)))?;
+    let exps = parse_all(&tokenize(src)?)?;
+    let mut result = RispExp::Bool(true);
+    for exp in &exps {
+        result = eval(exp, env)?;
+    }
+    Ok(result)
+}
+
+fn repl(env: &EnvRef) {
     loop {
         print!("risp > ");
         io::stdout().flush().unwrap(); // flush to show prompt
@@ -232,42 +810,312 @@ fn main() {
             break;
         }
         match parse_and_eval(input_expr, env) {
-            Ok(res) => println!("// ðŸ”¥ => {}", res),
+            Ok(res) => println!("// 🔥 => {}", res),
             Err(e) => match e {
-                RispErr::Reason(msg) => println!("// ðŸ™€ => {}", msg),
+                RispErr::Reason(msg) => println!("// 🙀 => {}", msg),
             },
         }
     }
 }
 
+fn main() {
+    let env = default_env();
+    match std::env::args().nth(1) {
+        Some(path) => {
+            if let Err(RispErr::Reason(msg)) = run_file(&path, &env) {
+                eprintln!("// 🙀 => {}", msg);
+                std::process::exit(1);
+            }
+        }
+        None => repl(&env),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{default_env, parse_and_eval, tokenize, RispExp};
+    use crate::{default_env, parse_and_eval, run_file, tokenize, RispExp};
 
     #[test]
     fn tokenize_check() {
         assert_eq!(
-            tokenize("(+ 10 5)".to_string()),
+            tokenize("(+ 10 5)".to_string()).unwrap(),
             vec!["(", "+", "10", "5", ")"]
         )
     }
 
     #[test]
     fn eval_check() {
-        let env = &mut default_env();
+        let env = default_env();
         assert_eq!(
             format!(
                 "{}",
-                parse_and_eval("(+ 10 5 (- 10 3 3))".to_string(), env).unwrap()
+                parse_and_eval("(+ 10 5 (- 10 3 3))".to_string(), &env).unwrap()
             ),
             "19"
         );
         assert_eq!(
             format!(
                 "{}",
-                parse_and_eval("(> 6 4 3 1)".to_string(), env).unwrap()
+                parse_and_eval("(> 6 4 3 1)".to_string(), &env).unwrap()
+            ),
+            "true"
+        );
+    }
+
+    #[test]
+    fn lambda_check() {
+        let env = default_env();
+        assert_eq!(
+            format!(
+                "{}",
+                parse_and_eval("((fn (r) (+ r r)) 10)".to_string(), &env).unwrap()
+            ),
+            "20"
+        );
+    }
+
+    #[test]
+    fn lambda_keyword_alias_check() {
+        let env = default_env();
+        assert_eq!(
+            format!(
+                "{}",
+                parse_and_eval("((lambda (r) (* 3.14 r r)) 10)".to_string(), &env).unwrap()
+            ),
+            "314"
+        );
+    }
+
+    #[test]
+    fn def_and_if_check() {
+        let env = default_env();
+        parse_and_eval("(def a 5)".to_string(), &env).unwrap();
+        assert_eq!(
+            format!("{}", parse_and_eval("(if (> a 3) 1 0)".to_string(), &env).unwrap()),
+            "1"
+        );
+    }
+
+    #[test]
+    fn fib_check() {
+        let env = default_env();
+        parse_and_eval(
+            "(def fib (fn (n) (if (< n 2) n (+ (fib (- n 1)) (fib (- n 2))))))".to_string(),
+            &env,
+        )
+        .unwrap();
+        assert_eq!(
+            format!("{}", parse_and_eval("(fib 10)".to_string(), &env).unwrap()),
+            "55"
+        );
+    }
+
+    #[test]
+    fn tokenize_with_strings_and_comments_check() {
+        assert_eq!(
+            tokenize("(print \"a (b)\") ; a trailing comment".to_string()).unwrap(),
+            vec!["(", "print", "\"a (b)\"", ")"]
+        )
+    }
+
+    #[test]
+    fn unterminated_quoted_list_does_not_panic() {
+        assert!(tokenize("'(1 2".to_string()).is_err());
+    }
+
+    #[test]
+    fn string_eval_check() {
+        let env = default_env();
+        assert_eq!(
+            format!(
+                "{}",
+                parse_and_eval("\"hello\\nworld\"".to_string(), &env).unwrap()
+            ),
+            "\"hello\nworld\""
+        );
+    }
+
+    #[test]
+    fn equality_check() {
+        let env = default_env();
+        assert_eq!(
+            format!(
+                "{}",
+                parse_and_eval("(= \"risp\" \"risp\")".to_string(), &env).unwrap()
             ),
             "true"
         );
+        assert_eq!(
+            format!(
+                "{}",
+                parse_and_eval("(= \"risp\" \"other\")".to_string(), &env).unwrap()
+            ),
+            "false"
+        );
+    }
+
+    #[test]
+    fn arithmetic_builtins_check() {
+        let env = default_env();
+        assert_eq!(
+            format!("{}", parse_and_eval("(pow 2 (* 3 4))".to_string(), &env).unwrap()),
+            "4096"
+        );
+        assert_eq!(
+            format!("{}", parse_and_eval("(/ 10 2)".to_string(), &env).unwrap()),
+            "5"
+        );
+        assert_eq!(
+            format!("{}", parse_and_eval("(mod 10 3)".to_string(), &env).unwrap()),
+            "1"
+        );
+        assert!(parse_and_eval("(/ 1 0)".to_string(), &env).is_err());
+    }
+
+    #[test]
+    fn and_or_check() {
+        let env = default_env();
+        assert_eq!(
+            format!("{}", parse_and_eval("(and true false)".to_string(), &env).unwrap()),
+            "false"
+        );
+        assert_eq!(
+            format!("{}", parse_and_eval("(or false true)".to_string(), &env).unwrap()),
+            "true"
+        );
+        // short-circuiting: the unreached branch would error if evaluated
+        assert_eq!(
+            format!(
+                "{}",
+                parse_and_eval("(and false (car (list)))".to_string(), &env).unwrap()
+            ),
+            "false"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                parse_and_eval("(or true (car (list)))".to_string(), &env).unwrap()
+            ),
+            "true"
+        );
+    }
+
+    #[test]
+    fn quote_check() {
+        let env = default_env();
+        assert_eq!(
+            format!("{}", parse_and_eval("'(1 2 3)".to_string(), &env).unwrap()),
+            "(1,2,3)"
+        );
+        assert_eq!(
+            format!("{}", parse_and_eval("(quote foo)".to_string(), &env).unwrap()),
+            "foo"
+        );
+    }
+
+    #[test]
+    fn list_primitives_check() {
+        let env = default_env();
+        assert_eq!(
+            format!("{}", parse_and_eval("(car (list 1 2 3))".to_string(), &env).unwrap()),
+            "1"
+        );
+        assert_eq!(
+            format!("{}", parse_and_eval("(cdr (list 1 2 3))".to_string(), &env).unwrap()),
+            "(2,3)"
+        );
+        assert_eq!(
+            format!("{}", parse_and_eval("(cons 0 (list 1 2))".to_string(), &env).unwrap()),
+            "(0,1,2)"
+        );
+        assert_eq!(
+            format!("{}", parse_and_eval("(atom (list))".to_string(), &env).unwrap()),
+            "true"
+        );
+        assert_eq!(
+            format!("{}", parse_and_eval("(atom (list 1))".to_string(), &env).unwrap()),
+            "false"
+        );
+    }
+
+    #[test]
+    fn stdlib_check() {
+        let env = default_env();
+        assert_eq!(
+            format!("{}", parse_and_eval("(not false)".to_string(), &env).unwrap()),
+            "true"
+        );
+        assert_eq!(
+            format!("{}", parse_and_eval("(inc 4)".to_string(), &env).unwrap()),
+            "5"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                parse_and_eval("(map inc (list 1 2 3))".to_string(), &env).unwrap()
+            ),
+            "(2,3,4)"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                parse_and_eval("(filter not (list false true false))".to_string(), &env).unwrap()
+            ),
+            "(false,false)"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                parse_and_eval("(reduce + 0 (list 1 2 3 4))".to_string(), &env).unwrap()
+            ),
+            "10"
+        );
+    }
+
+    #[test]
+    fn run_file_check() {
+        let path = std::env::temp_dir().join("risp_run_file_check.risp");
+        std::fs::write(&path, "(def x 2)\n(def y 3)\n(+ x y)").unwrap();
+        let env = default_env();
+        assert_eq!(
+            format!("{}", run_file(path.to_str().unwrap(), &env).unwrap()),
+            "5"
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn tail_call_check() {
+        // count-up is self-recursive in tail position; without the `eval`
+        // trampoline this would overflow the native call stack long before
+        // reaching 1,000,000
+        let env = default_env();
+        parse_and_eval(
+            "(def count-up (fn (n limit) (if (< n limit) (count-up (+ n 1) limit) n)))"
+                .to_string(),
+            &env,
+        )
+        .unwrap();
+        assert_eq!(
+            format!(
+                "{}",
+                parse_and_eval("(count-up 0 1000000)".to_string(), &env).unwrap()
+            ),
+            "1000000"
+        );
+    }
+
+    #[test]
+    fn lambda_outer_scope_check() {
+        // the lambda body should still be able to reach `x` in the enclosing env
+        let env = default_env();
+        parse_and_eval("(def x 10)".to_string(), &env).unwrap();
+        assert_eq!(
+            format!(
+                "{}",
+                parse_and_eval("((fn (y) (+ x y)) 5)".to_string(), &env).unwrap()
+            ),
+            "15"
+        );
     }
 }